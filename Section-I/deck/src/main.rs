@@ -1,55 +1,226 @@
+use std::collections::VecDeque;
+use std::fmt;
+
 use rand::{seq::SliceRandom, thread_rng};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Suit {
+    Hearts,
+    Spades,
+    Clubs,
+    Diamonds,
+}
+
+impl Suit {
+    const ALL: [Suit; 4] = [Suit::Hearts, Suit::Spades, Suit::Clubs, Suit::Diamonds];
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Suit::Hearts => "hearts",
+            Suit::Spades => "spades",
+            Suit::Clubs => "clubs",
+            Suit::Diamonds => "diamonds",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+}
+
+impl Rank {
+    const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Rank::Ace => "ace",
+            Rank::Two => "two",
+            Rank::Three => "three",
+            Rank::Four => "four",
+            Rank::Five => "five",
+            Rank::Six => "six",
+            Rank::Seven => "seven",
+            Rank::Eight => "eight",
+            Rank::Nine => "nine",
+            Rank::Ten => "ten",
+            Rank::Jack => "jack",
+            Rank::Queen => "queen",
+            Rank::King => "king",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// A card value assignable to any game that wants to score hands.
+trait Scorable {
+    fn value(&self) -> u32;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Card {
+    suit: Suit,
+    rank: Rank,
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of {}", self.rank, self.suit)
+    }
+}
+
+impl Scorable for Card {
+    fn value(&self) -> u32 {
+        self.rank as u32 + 1
+    }
+}
+
 #[derive(Debug)]
 struct Deck {
-    cards: Vec<String>,
+    cards: Vec<Card>,
 }
 
-//this is inherent implientation with impl
-// impl is a keyword used to create an implementation block that add function to the struct.
-//they have same name as struct that's why its called inherent implementation.
-// impl is used to create methods and functions that are associated with the struct.
 impl Deck {
-    // return type inotation with return type Deck or Self as function is inside Deck and returning it to Dect parent.
-    //this is associated function
     fn new() -> Self {
-        //following are area the has fixed values
-        let suits = ["hearts", "spades", "clubs", "diamonds"];
-        let values = [
-            "ace", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "jack",
-            "queen", "king",
-        ];
-
-        //below is vector arrays that has dinamic array
-        // let mut cards = vec::new(); /*you can also use this */
-        let mut cards = vec![]; // add mute to make the binding mutable.
-
-        for suit in suits {
-            for value in values {
-                let card = format!("{} of {}", value, suit); //format for joining.
-                cards.push(card);
+        let mut cards = vec![];
+
+        for suit in Suit::ALL {
+            for rank in Rank::ALL {
+                cards.push(Card { suit, rank });
             }
         }
 
-        // return Deck { cards }; /* return the deck in normal way */
-        Deck { cards } /* this is return with semicolon dropped */
+        Deck { cards }
     }
 
     fn shuffle(&mut self) {
         let mut rng = thread_rng();
         self.cards.shuffle(&mut rng);
     }
+}
+
+#[derive(Debug)]
+struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    fn new() -> Self {
+        Hand { cards: vec![] }
+    }
+}
+
+#[derive(Debug)]
+enum DealError {
+    DeckExhausted { requested: usize, remaining: usize },
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DealError::DeckExhausted {
+                requested,
+                remaining,
+            } => write!(
+                f,
+                "cannot deal {} cards, only {} left in the deck",
+                requested, remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+// Owns a deck, the players' hands and a discard pile, so a full round can be
+// dealt and played without reaching back into `Deck` directly.
+struct Table {
+    deck: Deck,
+    hands: Vec<Hand>,
+    discard: VecDeque<Card>,
+}
+
+impl Table {
+    fn new(deck: Deck) -> Self {
+        Table {
+            deck,
+            hands: vec![],
+            discard: VecDeque::new(),
+        }
+    }
+
+    fn deal_round(&mut self, players: usize, cards_each: usize) -> Result<(), DealError> {
+        let requested = players * cards_each;
+        if requested > self.deck.cards.len() {
+            return Err(DealError::DeckExhausted {
+                requested,
+                remaining: self.deck.cards.len(),
+            });
+        }
 
-    fn deal(&mut self, num_card: usize) -> Vec<String> {
-        self.cards.split_off(self.cards.len() - num_card)
+        self.hands = (0..players).map(|_| Hand::new()).collect();
+        for _ in 0..cards_each {
+            for hand in self.hands.iter_mut() {
+                hand.cards.push(self.deck.cards.pop().expect("checked above"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Option<Card> {
+        self.deck.cards.pop()
+    }
+
+    fn discard(&mut self, card: Card) {
+        self.discard.push_front(card);
     }
 }
 
 fn main() {
     let mut dec = Deck::new();
     dec.shuffle();
-    // println!("Here is you deck:{:#?}", dec);
-    // require error handling
-    let cards = dec.deal(5);
-    println!("Here is you hand:{:#?}", cards);
+
+    let mut table = Table::new(dec);
+    table.deal_round(4, 5).expect("deck has enough cards");
+    println!("Here are the hands:{:#?}", table.hands);
+
+    if let Some(card) = table.draw() {
+        table.discard(card);
+    }
+    println!("Discard pile:{:#?}", table.discard);
+
+    let hand_value: u32 = table.hands[0].cards.iter().map(Scorable::value).sum();
+    println!("Player one's hand is worth {}", hand_value);
 }
@@ -1,9 +1,46 @@
+use std::fmt;
+
+use query::Relation;
+
+#[derive(Debug)]
+enum BankError {
+    InsufficientFunds { requested: i32, available: i32 },
+    AccountNotFound(u32),
+    NegativeAmount,
+}
+
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BankError::InsufficientFunds {
+                requested,
+                available,
+            } => write!(
+                f,
+                "cannot withdraw {}, only {} available",
+                requested, available
+            ),
+            BankError::AccountNotFound(id) => write!(f, "no account with id {}", id),
+            BankError::NegativeAmount => write!(f, "amount must not be negative"),
+        }
+    }
+}
+
+impl std::error::Error for BankError {}
+
+#[derive(Debug)]
+enum Transaction {
+    Deposit { amount: i32, balance: i32 },
+    Withdraw { amount: i32, balance: i32 },
+}
+
 #[derive(Debug)]
 
 struct Account {
     id: u32,
     balance: i32,
     holder: String,
+    history: Vec<Transaction>,
 }
 
 impl Account {
@@ -12,22 +49,40 @@ impl Account {
             id,
             balance: 0,
             holder,
+            history: vec![],
         }
     }
 
-    fn deposit(&mut self, amount: i32) -> i32 {
+    fn deposit(&mut self, amount: i32) -> Result<i32, BankError> {
+        if amount < 0 {
+            return Err(BankError::NegativeAmount);
+        }
+
         self.balance += amount;
-        self.balance
+        self.history.push(Transaction::Deposit {
+            amount,
+            balance: self.balance,
+        });
+        Ok(self.balance)
     }
 
-    fn withdraw(&mut self, amount: i32) -> i32 {
-        if self.balance >= amount {
-            self.balance -= amount;
-            self.balance
-        } else {
-            println!("Cannot withdraw due to insufficient funds");
-            self.balance
+    fn withdraw(&mut self, amount: i32) -> Result<i32, BankError> {
+        if amount < 0 {
+            return Err(BankError::NegativeAmount);
+        }
+        if amount > self.balance {
+            return Err(BankError::InsufficientFunds {
+                requested: amount,
+                available: self.balance,
+            });
         }
+
+        self.balance -= amount;
+        self.history.push(Transaction::Withdraw {
+            amount,
+            balance: self.balance,
+        });
+        Ok(self.balance)
     }
 
     fn summary(&self) -> String {
@@ -36,6 +91,63 @@ impl Account {
             self.id, self.holder, self.balance
         )
     }
+
+    fn statement(&self) -> String {
+        self.history
+            .iter()
+            .map(|tx| match tx {
+                Transaction::Deposit { amount, balance } => {
+                    format!("deposit {} -> balance {}", amount, balance)
+                }
+                Transaction::Withdraw { amount, balance } => {
+                    format!("withdraw {} -> balance {}", amount, balance)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct AccountBuilder {
+    id: u32,
+    holder: String,
+    opening_balance: i32,
+}
+
+impl AccountBuilder {
+    fn new() -> Self {
+        AccountBuilder {
+            id: 0,
+            holder: String::new(),
+            opening_balance: 0,
+        }
+    }
+
+    fn with_id(&mut self, id: u32) -> &mut Self {
+        self.id = id;
+        self
+    }
+
+    fn with_holder(&mut self, holder: &str) -> &mut Self {
+        self.holder = holder.to_string();
+        self
+    }
+
+    fn with_opening_balance(&mut self, balance: i32) -> &mut Self {
+        self.opening_balance = balance;
+        self
+    }
+
+    // Takes `&self` rather than `self` so it can be called at the end of a
+    // `&mut self` chain without hitting "cannot move out of borrowed content".
+    fn build(&self) -> Account {
+        Account {
+            id: self.id,
+            balance: self.opening_balance,
+            holder: self.holder.clone(),
+            history: vec![],
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -48,10 +160,22 @@ impl Bank {
         Bank { account: vec![] }
     }
 
+    fn builder(builders: Vec<AccountBuilder>) -> Self {
+        let account = builders.iter().map(AccountBuilder::build).collect();
+        Bank { account }
+    }
+
     fn add_account(&mut self, account: Account) {
         self.account.push(account);
     }
 
+    fn account(&self, id: u32) -> Result<&Account, BankError> {
+        self.account
+            .iter()
+            .find(|acc| acc.id == id)
+            .ok_or(BankError::AccountNotFound(id))
+    }
+
     fn total_balance(&self) -> i32 {
         self.account.iter().map(|acc| acc.balance).sum()
     }
@@ -59,20 +183,267 @@ impl Bank {
     fn summary(&self) -> Vec<String> {
         self.account.iter().map(|acc| acc.summary()).collect()
     }
+
+    // Seeds a query context with the bank's accounts as an (holder, balance)
+    // relation, ready for declarative, set-based reports.
+    fn query(&self) -> BankQuery {
+        let accounts = Relation::from_vec(
+            self.account
+                .iter()
+                .map(|acc| (acc.holder.clone(), acc.balance))
+                .collect(),
+        );
+        BankQuery {
+            accounts,
+            watchlist: Relation::from_vec(vec![]),
+        }
+    }
+}
+
+// A query context seeded from a `Bank`, exposing set-based reports built on
+// top of the `query` module's relational combinators.
+struct BankQuery {
+    accounts: Relation<(String, i32)>,
+    watchlist: Relation<String>,
+}
+
+impl BankQuery {
+    fn with_watchlist(mut self, holders: Vec<String>) -> Self {
+        self.watchlist = Relation::from_vec(holders);
+        self
+    }
+
+    // Accounts holding more than `min_balance` whose holder also appears on
+    // the watchlist.
+    fn watchlisted_over(&self, min_balance: i32) -> Vec<(String, i32)> {
+        let flush: Relation<(String, ())> =
+            Relation::from_vec(self.watchlist.iter().map(|h| (h.clone(), ())).collect());
+        let rich = query::filter(&self.accounts, |(_, balance)| *balance > min_balance);
+
+        query::join(&rich, &flush)
+            .iter()
+            .map(|(holder, balance, ())| (holder.clone(), *balance))
+            .collect()
+    }
+
+    // Accounts whose holder is absent from the watchlist.
+    fn not_watchlisted(&self) -> Vec<(String, i32)> {
+        query::antijoin(&self.accounts, &self.watchlist)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+// A small Datalog-style relational engine: facts live in sorted, deduplicated
+// `Relation`s and are combined with merge-walking `join`/`filter`/`antijoin`.
+// `Variable` additionally supports recursive rules by accumulating newly
+// derived tuples round over round until a fixpoint is reached.
+mod query {
+    use std::cmp::Ordering;
+
+    #[derive(Debug, Clone)]
+    pub struct Relation<Tuple: Ord> {
+        elements: Vec<Tuple>,
+    }
+
+    impl<Tuple: Ord> Relation<Tuple> {
+        pub fn from_vec(mut elements: Vec<Tuple>) -> Self {
+            elements.sort();
+            elements.dedup();
+            Relation { elements }
+        }
+
+        pub fn iter(&self) -> std::slice::Iter<'_, Tuple> {
+            self.elements.iter()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.elements.is_empty()
+        }
+    }
+
+    // Merge-walks two key-sorted relations, pairing every left tuple with
+    // every right tuple that shares its key.
+    pub fn join<K, V1, V2>(left: &Relation<(K, V1)>, right: &Relation<(K, V2)>) -> Relation<(K, V1, V2)>
+    where
+        K: Ord + Clone,
+        V1: Ord + Clone,
+        V2: Ord + Clone,
+    {
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < left.elements.len() && j < right.elements.len() {
+            let lk = &left.elements[i].0;
+            let rk = &right.elements[j].0;
+
+            match lk.cmp(rk) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    let i_end = (i..left.elements.len())
+                        .take_while(|&k| &left.elements[k].0 == lk)
+                        .last()
+                        .unwrap()
+                        + 1;
+                    let j_end = (j..right.elements.len())
+                        .take_while(|&k| &right.elements[k].0 == rk)
+                        .last()
+                        .unwrap()
+                        + 1;
+
+                    for li in i..i_end {
+                        for rj in j..j_end {
+                            result.push((
+                                left.elements[li].0.clone(),
+                                left.elements[li].1.clone(),
+                                right.elements[rj].1.clone(),
+                            ));
+                        }
+                    }
+
+                    i = i_end;
+                    j = j_end;
+                }
+            }
+        }
+
+        Relation::from_vec(result)
+    }
+
+    pub fn filter<Tuple, F>(relation: &Relation<Tuple>, mut predicate: F) -> Relation<Tuple>
+    where
+        Tuple: Ord + Clone,
+        F: FnMut(&Tuple) -> bool,
+    {
+        Relation::from_vec(
+            relation
+                .elements
+                .iter()
+                .filter(|tuple| predicate(tuple))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    // Keeps the tuples of `left` whose key does not occur in `right`. `right`
+    // is only ever borrowed, so it stays frozen for the whole walk and the
+    // result is deterministic regardless of call order.
+    pub fn antijoin<K, V>(left: &Relation<(K, V)>, right: &Relation<K>) -> Relation<(K, V)>
+    where
+        K: Ord + Clone,
+        V: Ord + Clone,
+    {
+        let mut result = vec![];
+        let mut j = 0;
+
+        for (key, value) in left.elements.iter() {
+            while j < right.elements.len() && &right.elements[j] < key {
+                j += 1;
+            }
+            if j >= right.elements.len() || &right.elements[j] != key {
+                result.push((key.clone(), value.clone()));
+            }
+        }
+
+        Relation::from_vec(result)
+    }
+
+    // Accumulates tuples derived by recursive rules across rounds: each call to
+    // `changed` folds the previous `recent` batch into `stable` and makes the
+    // newly inserted tuples the next `recent` batch, until nothing new appears.
+    // The batches are private; only `complete` reads them.
+    pub struct Variable<Tuple: Ord + Clone> {
+        stable: Vec<Relation<Tuple>>,
+        recent: Relation<Tuple>,
+        to_add: Vec<Relation<Tuple>>,
+    }
+
+    impl<Tuple: Ord + Clone> Variable<Tuple> {
+        pub fn new() -> Self {
+            Variable {
+                stable: vec![],
+                recent: Relation::from_vec(vec![]),
+                to_add: vec![],
+            }
+        }
+
+        pub fn insert(&mut self, relation: Relation<Tuple>) {
+            self.to_add.push(relation);
+        }
+
+        // Folds `recent` into `stable`, then promotes any freshly inserted
+        // tuples (minus ones already stable) into the new `recent` batch.
+        // Returns whether anything new was produced this round.
+        pub fn changed(&mut self) -> bool {
+            if !self.recent.is_empty() {
+                let finished = std::mem::replace(&mut self.recent, Relation::from_vec(vec![]));
+                self.stable.push(finished);
+            }
+
+            let mut fresh: Vec<Tuple> = self.to_add.drain(..).flat_map(|r| r.elements).collect();
+            fresh.sort();
+            fresh.dedup();
+            fresh.retain(|tuple| {
+                !self
+                    .stable
+                    .iter()
+                    .any(|batch| batch.elements.binary_search(tuple).is_ok())
+            });
+
+            if fresh.is_empty() {
+                false
+            } else {
+                self.recent = Relation::from_vec(fresh);
+                true
+            }
+        }
+
+        // Drains the accumulated rounds into a single stable relation.
+        pub fn complete(self) -> Relation<Tuple> {
+            let mut elements: Vec<Tuple> = self.stable.into_iter().flat_map(|r| r.elements).collect();
+            elements.extend(self.recent.elements);
+            Relation::from_vec(elements)
+        }
+    }
+
+    // Repeatedly runs `round` (which should return whether it produced any
+    // new tuples) until it reports no further progress.
+    pub fn iterate_to_fixpoint<F: FnMut() -> bool>(mut round: F) {
+        while round() {}
+    }
 }
 
 fn main() {
+    let mut alice_builder = AccountBuilder::new();
+    alice_builder
+        .with_id(1)
+        .with_holder("Alice")
+        .with_opening_balance(0);
+    let mut account1 = alice_builder.build();
+
+    let mut bob_builder = AccountBuilder::new();
+    bob_builder
+        .with_id(2)
+        .with_holder("Bob")
+        .with_opening_balance(0);
+    let mut account2 = bob_builder.build();
+
     let mut bank = Bank::new();
-    let mut account1 = Account::new(1, String::from("Alice"));
-    let mut account2 = Account::new(2, String::from("Bob"));
 
-    account1.deposit(1000);
-    account1.withdraw(400);
-    account2.deposit(2000);
-    account2.withdraw(250);
+    account1.deposit(1000).expect("deposit should succeed");
+    account1.withdraw(400).expect("withdraw should succeed");
+    account2.deposit(2000).expect("deposit should succeed");
+    account2.withdraw(250).expect("withdraw should succeed");
+
+    if let Err(err) = account1.withdraw(10_000) {
+        println!("Withdrawal failed: {}", err);
+    }
 
     let account1summary = account1.summary();
     println!("{}", account1summary);
+    println!("{}", account1.statement());
 
     bank.add_account(account1);
     bank.add_account(account2);
@@ -82,4 +453,58 @@ fn main() {
     println!("{:#?}", bank);
     println!("Total balance in bank: {}", total);
     println!("Account summaries: {:#?}", summaries);
+
+    match bank.account(1) {
+        Ok(acc) => println!("Found account 1: {}", acc.summary()),
+        Err(err) => println!("Lookup failed: {}", err),
+    }
+    if let Err(err) = bank.account(99) {
+        println!("Lookup failed: {}", err);
+    }
+
+    // `Account::new` still works for callers who don't need the builder.
+    let mut eve = Account::new(5, String::from("Eve"));
+    eve.deposit(100).expect("deposit should succeed");
+    println!("{}", eve.summary());
+
+    // A whole bank can also be assembled in one expression from builders.
+    let mut carol_builder = AccountBuilder::new();
+    carol_builder
+        .with_id(3)
+        .with_holder("Carol")
+        .with_opening_balance(500);
+
+    let mut dave_builder = AccountBuilder::new();
+    dave_builder
+        .with_id(4)
+        .with_holder("Dave")
+        .with_opening_balance(750);
+
+    let seeded_bank = Bank::builder(vec![carol_builder, dave_builder]);
+    println!("Seeded bank summaries: {:#?}", seeded_bank.summary());
+
+    let report = seeded_bank
+        .query()
+        .with_watchlist(vec!["Carol".to_string()]);
+    println!(
+        "Watchlisted accounts over 100: {:#?}",
+        report.watchlisted_over(100)
+    );
+    println!("Accounts not on the watchlist: {:#?}", report.not_watchlisted());
+
+    // `Variable`/`iterate_to_fixpoint` accept the same kind of query even
+    // when it only takes a single round to settle.
+    let mut flagged = query::Variable::new();
+    flagged.insert(Relation::from_vec(
+        report
+            .watchlisted_over(100)
+            .into_iter()
+            .map(|(holder, _)| holder)
+            .collect(),
+    ));
+    query::iterate_to_fixpoint(|| flagged.changed());
+    println!(
+        "Flagged holders: {:#?}",
+        flagged.complete().iter().collect::<Vec<_>>()
+    );
 }
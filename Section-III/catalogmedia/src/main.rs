@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 #[derive(Debug)]
 
 enum Media {
@@ -6,9 +8,69 @@ enum Media {
     Audiobook { title: String },
 }
 
-fn print_media(media: Media) {
+// Gives each media kind a title and a kind label, which the catalog's `titles`
+// and `count_by_kind` read through this trait. `by_author` matches on the
+// `Media` variants directly, so a new variant also needs an arm there.
+trait Describable {
+    fn title(&self) -> &str;
+    fn kind(&self) -> &'static str;
+}
+
+impl Describable for Media {
+    fn title(&self) -> &str {
+        match self {
+            Media::Book { title, .. } => title,
+            Media::Movie { title, .. } => title,
+            Media::Audiobook { title } => title,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Media::Book { .. } => "book",
+            Media::Movie { .. } => "movie",
+            Media::Audiobook { .. } => "audiobook",
+        }
+    }
+}
+
+fn print_media(media: &Media) {
     println!("{:#?}", media);
 }
+
+struct MediaCatalog {
+    items: Vec<Media>,
+}
+
+impl MediaCatalog {
+    fn new(items: Vec<Media>) -> Self {
+        MediaCatalog { items }
+    }
+
+    fn by_author(&self, name: &str) -> Vec<&Media> {
+        self.items
+            .iter()
+            .filter(|media| match media {
+                Media::Book { author, .. } => author == name,
+                Media::Movie { director, .. } => director == name,
+                Media::Audiobook { .. } => false,
+            })
+            .collect()
+    }
+
+    fn titles(&self) -> Vec<&str> {
+        self.items.iter().map(Describable::title).collect()
+    }
+
+    fn count_by_kind(&self) -> HashMap<&'static str, usize> {
+        let mut counts = HashMap::new();
+        for media in &self.items {
+            *counts.entry(media.kind()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
 fn main() {
     let bad_book = Media::Book {
         title: String::from("Bad Book"),
@@ -23,7 +85,14 @@ fn main() {
     let cool_audiobook = Media::Audiobook {
         title: String::from("Cool Audiobook"),
     };
-    print_media(good_movie);
-    print_media(cool_audiobook);
-    print_media(bad_book);
+
+    print_media(&good_movie);
+    print_media(&cool_audiobook);
+    print_media(&bad_book);
+
+    let catalog = MediaCatalog::new(vec![bad_book, good_movie, cool_audiobook]);
+
+    println!("Titles: {:#?}", catalog.titles());
+    println!("By Unknown: {:#?}", catalog.by_author("Unknown"));
+    println!("Count by kind: {:#?}", catalog.count_by_kind());
 }